@@ -1,6 +1,7 @@
 use std::ops::Index;
 
 mod minesweeper;
+mod replay;
 mod ui;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -1,18 +1,21 @@
 use web_time::SystemTime;
 use rand::seq::IteratorRandom;
 use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use tinyvec::ArrayVec;
 
-#[derive(Debug,Clone, Copy, PartialEq)]
+#[derive(Debug,Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum GameStatus { Win, GameOver, Running }
 
 /// Settings for the Minesweeper game.
-pub struct Settings { 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Settings {
     /// Width of the game board.
-    pub dx: usize, 
+    pub dx: usize,
     /// Height of the game board.
-    pub dy: usize, 
+    pub dy: usize,
     /// Number of mines on the game board.
-    pub mine_count: usize 
+    pub mine_count: usize
 }
 #[allow(dead_code)]
 pub const BEGINNER_SETTINGS: Settings = Settings { dx: 8, dy: 8, mine_count: 10 };
@@ -21,8 +24,13 @@ pub const INTERMEDIATE_SETTINGS: Settings = Settings { dx: 16, dy: 16, mine_coun
 #[allow(dead_code)]
 pub const EXPERT_SETTINGS: Settings = Settings { dx: 30, dy: 16, mine_count: 99 };
 
+/// Smallest board dimension `reconfigure` will accept.
+const MIN_DIM: usize = 4;
+/// Largest board dimension `reconfigure` will accept.
+const MAX_DIM: usize = 100;
+
 /// Represents a single field on the Minesweeper game board.
-#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[derive(Copy, Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct Field {
     /// Indicates if the field is a mine.
     is_mine: bool,
@@ -62,6 +70,32 @@ impl Field {
     }
 }
 
+/// A typed, replayable player action.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    Reveal { x: usize, y: usize },
+    Flag { x: usize, y: usize },
+    Chord { x: usize, y: usize },
+    Restart,
+}
+
+/// A `GameEvent` tagged with the millisecond offset from game start it occurred at.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    /// Milliseconds since the game was (re)started.
+    pub millis: u64,
+    pub event: GameEvent,
+}
+
+/// A logically guaranteed next move, deduced by `Minesweeper::hint`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hint {
+    /// The field at the given coordinates is guaranteed not to be a mine.
+    SafeReveal { x: usize, y: usize },
+    /// The field at the given coordinates is guaranteed to be a mine.
+    Mine { x: usize, y: usize },
+}
+
 /// Represents the Minesweeper game.
 pub struct Minesweeper {
     status: GameStatus,
@@ -79,6 +113,13 @@ pub struct Minesweeper {
     flagged_count: usize,
     /// Number of fields that are mines and flagged.
     flagged_mines_count: usize,
+    /// Whether mines have been placed for the current game yet.
+    ///
+    /// Mine placement is deferred to the first `reveal` so that the first click can never be a
+    /// mine.
+    mines_placed: bool,
+    /// Log of every player action taken since the last restart, for save/replay purposes.
+    record: Vec<TimedEvent>,
     /// 2D vector representing the game board.
     board: Vec<Vec<Field>>,
 }
@@ -93,6 +134,8 @@ pub fn new(set: Settings) -> Minesweeper {
         revealed_count: 0,
         flagged_count: 0,
         flagged_mines_count: 0,
+        mines_placed: false,
+        record: Vec::new(),
         board: vec![vec![Field::default(); set.dx as usize]; set.dy as usize],
     };
 
@@ -110,6 +153,14 @@ impl Minesweeper {
         return self.mine_count;
     }
 
+    pub fn dx(&self) -> usize {
+        return self.dx;
+    }
+
+    pub fn dy(&self) -> usize {
+        return self.dy;
+    }
+
     pub fn board_clone(&self) -> Vec<Vec<Field>> {
         return self.board.clone();
     }
@@ -118,6 +169,54 @@ impl Minesweeper {
         return self.flagged_count;
     }
 
+    /// Returns the full recorded move log for the current game, for saving or replay.
+    pub fn record(&self) -> &[TimedEvent] {
+        return &self.record;
+    }
+
+    /// Returns the coordinates of every mine on the board, regardless of reveal state.
+    ///
+    /// Used to seed a `MinesweeperReplay` so a finished game can be deterministically replayed.
+    pub fn mine_layout(&self) -> Vec<(usize, usize)> {
+        let mut layout = Vec::new();
+        for y in 0..self.dy {
+            for x in 0..self.dx {
+                if self.board[y][x].is_mine {
+                    layout.push((x, y));
+                }
+            }
+        }
+        return layout;
+    }
+
+    /// Appends an event to the move log, timestamped relative to `start`.
+    fn push_event(&mut self, event: GameEvent) {
+        let millis = SystemTime::now().duration_since(self.start).unwrap().as_millis() as u64;
+        self.record.push(TimedEvent { millis, event });
+    }
+
+    /// Returns the in-bounds coordinates of the (up to 8) cells surrounding `(x, y)`.
+    fn neighbors(&self, x: usize, y: usize) -> ArrayVec<[(usize, usize); 8]> {
+        let mut result = ArrayVec::new();
+        // upper left
+        if y > 0 && x > 0 { result.push((x-1, y-1)); }
+        // upper
+        if y > 0 { result.push((x, y-1)); }
+        // upper right
+        if y > 0 && x < self.dx-1 { result.push((x+1, y-1)); }
+        // left
+        if x > 0 { result.push((x-1, y)); }
+        // right
+        if x < self.dx-1 { result.push((x+1, y)); }
+        // lower left
+        if y < self.dy-1 && x > 0 { result.push((x-1, y+1)); }
+        // lower
+        if y < self.dy-1 { result.push((x, y+1)); }
+        // lower right
+        if y < self.dy-1 && x < self.dx-1 { result.push((x+1, y+1)); }
+        return result;
+    }
+
     /// Returns the number of seconds the game has been running.
     ///
     /// This method keeps running even if the game status != Running.
@@ -128,7 +227,9 @@ impl Minesweeper {
     /// (Re)starts the game.
     ///
     /// This method resets the game status to `Running`, sets the start time to the current time,
-    /// resets the revealed field count, and reinitializes the game board with new mines.
+    /// resets the revealed field count, and clears the game board.
+    /// Mines are not placed yet: they are planted lazily by `reveal` on the first click, so the
+    /// very first reveal can never be a mine.
     /// Initial game settings are left untouched.
     pub fn restart(&mut self) {
         self.status = GameStatus::Running;
@@ -136,44 +237,82 @@ impl Minesweeper {
         self.revealed_count = 0;
         self.flagged_count = 0;
         self.flagged_mines_count = 0;
+        self.mines_placed = false;
+        self.record = vec![TimedEvent { millis: 0, event: GameEvent::Restart }];
 
         // reset fields
         self.board = vec![vec![Field::default(); self.dx as usize]; self.dy as usize];
 
-        // generate mines
-        let mines = (0..self.dx * self.dy).choose_multiple(&mut thread_rng(), self.mine_count as usize);
+        // debug print board
+        self.print_board();
+    }
+
+    /// Rebuilds the board with new settings and restarts the game.
+    ///
+    /// Dimensions are clamped to `[MIN_DIM, MAX_DIM]`. The mine count is clamped to
+    /// `[1, dx*dy-9]`, not just `dx*dy-1`: `place_mines` excludes the first-clicked cell and its
+    /// up to 8 neighbors from mine placement, so leaving fewer than 9 cells free would make it
+    /// impossible to actually place the requested number of mines, leaving `mine_count` larger
+    /// than the board can ever satisfy and the game permanently unwinnable.
+    pub fn reconfigure(&mut self, set: Settings) {
+        self.dx = set.dx.clamp(MIN_DIM, MAX_DIM);
+        self.dy = set.dy.clamp(MIN_DIM, MAX_DIM);
+        let max_mines = (self.dx * self.dy).saturating_sub(9).max(1);
+        self.mine_count = set.mine_count.clamp(1, max_mines);
+
+        self.restart();
+    }
+
+    /// Plants `mine_count` mines, avoiding the given coordinates and its 8 neighbors, then
+    /// computes `adjacent_mines` for every field.
+    ///
+    /// This is called lazily by `reveal` on the first click of a game so that the first reveal
+    /// always opens a safe region.
+    fn place_mines(&mut self, x: usize, y: usize) {
+        let mut excluded: Vec<(usize, usize)> = self.neighbors(x, y).to_vec();
+        excluded.push((x, y));
+
+        // generate mines, excluding the clicked cell and its neighbors
+        let candidates = (0..self.dx * self.dy).filter(|i| {
+            let row = i / self.dx;
+            let col = i % self.dx;
+            !excluded.contains(&(col, row))
+        });
+        let mines = candidates.choose_multiple(&mut thread_rng(), self.mine_count as usize);
         for i in mines {
             let row = i / self.dx;
             let col = i % self.dx;
             self.board[row as usize][col as usize].is_mine = true;
         }
 
-        // calculate adjacent_mines for every field
-        for y in 0..self.dy as usize {
-            for x in 0..self.dx as usize {
+        self.recompute_adjacency();
+        self.mines_placed = true;
+    }
+
+    /// Places mines at exactly the given coordinates instead of choosing them randomly, then
+    /// computes `adjacent_mines` for every field.
+    ///
+    /// Used by `MinesweeperReplay` to deterministically reconstruct a previously recorded game.
+    pub(crate) fn seed_mines(&mut self, layout: &[(usize, usize)]) {
+        for &(x, y) in layout {
+            self.board[y][x].is_mine = true;
+        }
+
+        self.recompute_adjacency();
+        self.mines_placed = true;
+    }
+
+    /// Recomputes `adjacent_mines` for every field from the current mine placement.
+    fn recompute_adjacency(&mut self) {
+        for y in 0..self.dy {
+            for x in 0..self.dx {
                 if self.board[y][x].is_mine {
-                    // upper left
-                    if y > 0 && x > 0 { self.board[y-1][x-1].adjacent_mines += 1; }
-                    // upper
-                    if y > 0 { self.board[y-1][x].adjacent_mines += 1; }
-                    // upper right
-                    if y > 0 && x < self.dx-1 { self.board[y-1][x+1].adjacent_mines += 1; }
-                    // left
-                    if x > 0 { self.board[y][x-1].adjacent_mines += 1; }
-                    // right
-                    if x < self.dx-1 { self.board[y][x+1].adjacent_mines += 1; }
-                    // lower left
-                    if y < self.dy-1 && x > 0 { self.board[y+1][x-1].adjacent_mines += 1; }
-                    // lower
-                    if y < self.dy-1 { self.board[y+1][x].adjacent_mines += 1; }
-                    // lower right
-                    if y < self.dy-1 && x < self.dx-1 { self.board[y+1][x+1].adjacent_mines += 1; }
+                    for (nx, ny) in self.neighbors(x, y) {
+                        self.board[ny][nx].adjacent_mines += 1;
+                    }
                 }
             }
         }
-
-        // debug print board
-        self.print_board();
     }
 
     /// Reveals the field at the given coordinates.
@@ -187,11 +326,33 @@ impl Minesweeper {
             return;
         }
 
-        let f = &mut self.board[y][x];
-        if f.is_revealed || f.is_flagged {
+        if !self.mines_placed {
+            self.place_mines(x, y);
+            self.start = SystemTime::now();
+        }
+
+        if self.board[y][x].is_revealed || self.board[y][x].is_flagged {
+            return
+        }
+        self.push_event(GameEvent::Reveal { x, y });
+
+        self.reveal_cell(x, y);
+    }
+
+    /// Reveals the field at the given coordinates without recording a `Reveal` event.
+    ///
+    /// Used by `chord`, whose neighbor sweep is already covered by the single `Chord` event it
+    /// records, so routing those reveals through the public `reveal` would log a redundant entry
+    /// per neighbor. Assumes mines have already been placed; `reveal` places them and resets
+    /// `start` before its first call, so the first `Reveal` event and every event after it are
+    /// timestamped against that same `start` (flags placed before the first reveal were
+    /// timestamped against the earlier restart time).
+    fn reveal_cell(&mut self, x: usize, y: usize) {
+        if self.board[y][x].is_revealed || self.board[y][x].is_flagged {
             return
         }
-        
+
+        let f = &mut self.board[y][x];
         if f.is_mine { // mine => game over
             f.is_revealed = true;
             self.revealed_count += 1;
@@ -210,37 +371,63 @@ impl Minesweeper {
 
     /// Reveals all connected fields with zero adjacent mines starting from the given coordinates.
     ///
-    /// This method recursively reveals fields with zero adjacent mines, stopping when it encounters
-    /// a field with non-zero adjacent mines or a field that has already been revealed.
+    /// This method floods outward from a zero field using an explicit work-stack (rather than
+    /// recursion), so opening a large zero-region on an arbitrarily large board can't blow the
+    /// call stack. It stops spreading past any field with non-zero adjacent mines or a field
+    /// that has already been revealed.
     fn reveal_zeros(&mut self, x: usize, y: usize) {
-        let f = &mut self.board[y][x];
+        let mut stack = vec![(x, y)];
 
-        if f.is_revealed || f.is_flagged { // trivial cases
-            return
+        while let Some((x, y)) = stack.pop() {
+            let f = &mut self.board[y][x];
+
+            if f.is_revealed || f.is_flagged { // trivial cases
+                continue
+            }
+
+            f.is_revealed = true;
+            self.revealed_count += 1;
+            if f.adjacent_mines != 0 { // trivial case: field is not a zero
+                continue
+            }
+
+            stack.extend(self.neighbors(x, y));
         }
+    }
 
-        f.is_revealed = true;
-        self.revealed_count += 1;
-        if f.adjacent_mines != 0 { // trivial case: field is not a zero
-            return
+    /// Chords the revealed field at the given coordinates.
+    ///
+    /// Chording only has an effect on a revealed, non-zero field whose flagged neighbor count
+    /// matches its `adjacent_mines`. In that case every remaining unflagged, unrevealed neighbor
+    /// is revealed at once, the same way a direct click on it would be handled. If the player
+    /// misflagged a neighbor, this can reveal a mine and end the game.
+    pub fn chord(&mut self, x: usize, y: usize) {
+        if self.status != GameStatus::Running {
+            return;
         }
 
-        // upper left
-        if y > 0 && x > 0 { self.reveal_zeros(x-1, y-1); }
-        // upper
-        if y > 0 { self.reveal_zeros(x, y-1); }
-        // upper right
-        if y > 0 && x < self.dx-1 { self.reveal_zeros(x+1, y-1); }
-        // left
-        if x > 0 { self.reveal_zeros(x-1, y); }
-        // right
-        if x < self.dx-1 { self.reveal_zeros(x+1, y); }
-        // lower left
-        if y < self.dy-1 && x > 0 { self.reveal_zeros(x-1, y+1); }
-        // lower
-        if y < self.dy-1 { self.reveal_zeros(x, y+1); }
-        // lower right
-        if y < self.dy-1 && x < self.dx-1 { self.reveal_zeros(x+1, y+1); }
+        let f = self.board[y][x];
+        if !f.is_revealed || f.adjacent_mines == 0 {
+            return;
+        }
+
+        let neighbors = self.neighbors(x, y);
+        let flagged_neighbors = neighbors.iter().filter(|&&(nx, ny)| self.board[ny][nx].is_flagged).count();
+        if flagged_neighbors != f.adjacent_mines {
+            return;
+        }
+        self.push_event(GameEvent::Chord { x, y });
+
+        // reveal every remaining unflagged, unrevealed neighbor; routed through reveal_cell so
+        // the chord is recorded as the single Chord event above rather than one Reveal per neighbor.
+        // Stop as soon as a neighbor ends the game so a mine revealed mid-chord can't have its
+        // GameOver status silently overwritten by a later neighbor completing check_win's Win condition.
+        for (nx, ny) in neighbors {
+            if self.status != GameStatus::Running {
+                break;
+            }
+            self.reveal_cell(nx, ny);
+        }
     }
 
     /// Flags or unflags the field at the given coordinates.
@@ -254,11 +441,12 @@ impl Minesweeper {
             return;
         }
 
-        let f = &mut self.board[y][x];
-        if f.is_revealed { // trivial case: field is already revealed
+        if self.board[y][x].is_revealed { // trivial case: field is already revealed
             return;
         }
+        self.push_event(GameEvent::Flag { x, y });
 
+        let f = &mut self.board[y][x];
         if f.is_flagged { // unflag
             f.is_flagged = false;
 
@@ -280,6 +468,53 @@ impl Minesweeper {
         self.print_board();
     }
 
+    /// Runs single-cell constraint deduction over the currently revealed board to find a
+    /// logically guaranteed next move.
+    ///
+    /// For every revealed numbered field, compares its `adjacent_mines` against its flagged
+    /// neighbor count and its unrevealed, unflagged neighbor count:
+    /// * if every remaining unrevealed, unflagged neighbor must be a mine to account for
+    ///   `adjacent_mines`, the first of them is returned as a guaranteed `Hint::Mine`.
+    /// * if every mine around the field is already flagged, its remaining unrevealed, unflagged
+    ///   neighbors are guaranteed safe and the first of them is returned as a `Hint::SafeReveal`.
+    ///
+    /// Returns the first deduction found, or `None` if no guaranteed move exists.
+    pub fn hint(&self) -> Option<Hint> {
+        for y in 0..self.dy {
+            for x in 0..self.dx {
+                let f = self.board[y][x];
+                if !f.is_revealed || f.adjacent_mines == 0 {
+                    continue;
+                }
+
+                let neighbors = self.neighbors(x, y);
+                let flagged_neighbors = neighbors.iter()
+                    .filter(|&&(nx, ny)| self.board[ny][nx].is_flagged)
+                    .count();
+                let unrevealed_unflagged: Vec<(usize, usize)> = neighbors.iter()
+                    .copied()
+                    .filter(|&(nx, ny)| !self.board[ny][nx].is_revealed && !self.board[ny][nx].is_flagged)
+                    .collect();
+
+                if unrevealed_unflagged.is_empty() || flagged_neighbors > f.adjacent_mines {
+                    continue;
+                }
+
+                if f.adjacent_mines - flagged_neighbors == unrevealed_unflagged.len() {
+                    let (x, y) = unrevealed_unflagged[0];
+                    return Some(Hint::Mine { x, y });
+                }
+
+                if f.adjacent_mines == flagged_neighbors {
+                    let (x, y) = unrevealed_unflagged[0];
+                    return Some(Hint::SafeReveal { x, y });
+                }
+            }
+        }
+
+        return None;
+    }
+
     // Checks if the game is won.
     fn check_win(&mut self) {
         // determine if all fields except mines are revealed
@@ -324,3 +559,25 @@ impl Minesweeper {
         println!();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chord_ends_game_on_misflagged_mine() {
+        let mut ms = new(Settings { dx: 3, dy: 2, mine_count: 1 });
+        ms.seed_mines(&[(1, 0)]);
+
+        ms.reveal(1, 1);
+        ms.flag(2, 1); // misflag: (2, 1) is not actually a mine
+
+        ms.chord(1, 1);
+
+        assert_eq!(ms.status(), GameStatus::GameOver);
+        // (2, 0) is only reached after the mine in chord's neighbor sweep; it must stay
+        // unrevealed so a later reveal in the same chord can't complete the board and flip
+        // status back to Win.
+        assert!(!ms.board_clone()[0][2].is_revealed());
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use web_time::SystemTime;
+
+use crate::minesweeper::{self, Field, GameEvent, GameStatus, Minesweeper, Settings, TimedEvent};
+
+/// A serializable snapshot of a `Minesweeper` game, suitable for saving to disk and replaying
+/// later with `MinesweeperReplay`. There is no path that resumes live play from a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinesweeperSnapshot {
+    pub settings: Settings,
+    pub status: GameStatus,
+    pub mine_layout: Vec<(usize, usize)>,
+    pub record: Vec<TimedEvent>,
+}
+
+impl MinesweeperSnapshot {
+    /// Captures a snapshot of the given game, including its full recorded move history.
+    pub fn capture(ms: &Minesweeper) -> MinesweeperSnapshot {
+        return MinesweeperSnapshot {
+            settings: Settings { dx: ms.dx(), dy: ms.dy(), mine_count: ms.mine_count() },
+            status: ms.status(),
+            mine_layout: ms.mine_layout(),
+            record: ms.record().to_vec(),
+        };
+    }
+
+    /// Serializes the snapshot to a JSON string.
+    pub fn save(&self) -> serde_json::Result<String> {
+        return serde_json::to_string(self);
+    }
+
+    /// Deserializes a snapshot from a JSON string.
+    pub fn load(json: &str) -> serde_json::Result<MinesweeperSnapshot> {
+        return serde_json::from_str(json);
+    }
+}
+
+/// Replays a saved game's recorded moves move by move.
+///
+/// Re-applies the recorded event list against a fresh board seeded with the original mine
+/// layout, so a finished (or in-progress) game can be watched back deterministically.
+pub struct MinesweeperReplay {
+    events: Vec<TimedEvent>,
+    cursor: usize,
+    started_at: SystemTime,
+    ms: Minesweeper,
+}
+
+impl MinesweeperReplay {
+    /// Builds a replay from a saved snapshot.
+    pub fn from_snapshot(snapshot: &MinesweeperSnapshot) -> MinesweeperReplay {
+        let mut ms = minesweeper::new(snapshot.settings);
+        ms.seed_mines(&snapshot.mine_layout);
+
+        return MinesweeperReplay {
+            events: snapshot.record.clone(),
+            cursor: 0,
+            started_at: SystemTime::now(),
+            ms,
+        };
+    }
+
+    /// Returns true once every recorded event has been applied.
+    pub fn is_finished(&self) -> bool {
+        return self.cursor >= self.events.len();
+    }
+
+    /// Returns the millisecond offset of the next event still to be applied, if any.
+    ///
+    /// Intended to be polled from the UI's `on_tick` callback to pace replay stepping.
+    pub fn next_event_millis(&self) -> Option<u64> {
+        return self.events.get(self.cursor).map(|e| e.millis);
+    }
+
+    /// Applies every recorded event that is due by now, paced against how long the replay has
+    /// actually been running, and returns the resulting board.
+    ///
+    /// Meant to be called once per UI tick: each call steps forward through `next_event_millis`
+    /// as far as elapsed wall-clock time allows, so playback speed reflects the original game's
+    /// timing rather than stepping one event per tick regardless of when it was recorded.
+    pub fn advance(&mut self) -> Vec<Vec<Field>> {
+        let elapsed = SystemTime::now().duration_since(self.started_at).unwrap().as_millis() as u64;
+
+        while let Some(next_millis) = self.next_event_millis() {
+            if next_millis > elapsed {
+                break;
+            }
+            self.step();
+        }
+
+        return self.ms.board_clone();
+    }
+
+    /// Applies the next recorded event, if any, and returns the resulting board.
+    pub fn step(&mut self) -> Vec<Vec<Field>> {
+        if !self.is_finished() {
+            match self.events[self.cursor].event {
+                GameEvent::Reveal { x, y } => self.ms.reveal(x, y),
+                GameEvent::Flag { x, y } => self.ms.flag(x, y),
+                GameEvent::Chord { x, y } => self.ms.chord(x, y),
+                GameEvent::Restart => (), // the opening marker of the log; nothing to replay
+            }
+            self.cursor += 1;
+        }
+
+        return self.ms.board_clone();
+    }
+
+    pub fn board_clone(&self) -> Vec<Vec<Field>> {
+        return self.ms.board_clone();
+    }
+
+    pub fn status(&self) -> GameStatus {
+        return self.ms.status();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minesweeper::new;
+
+    #[test]
+    fn replay_reproduces_recorded_game() {
+        let mut ms = new(Settings { dx: 5, dy: 5, mine_count: 3 });
+
+        ms.reveal(0, 0);
+        ms.flag(4, 4);
+        ms.reveal(1, 0);
+
+        let snapshot = MinesweeperSnapshot::capture(&ms);
+        let mut replay = MinesweeperReplay::from_snapshot(&snapshot);
+        while !replay.is_finished() {
+            replay.step();
+        }
+
+        assert_eq!(replay.board_clone(), ms.board_clone());
+        assert_eq!(replay.status(), ms.status());
+    }
+}
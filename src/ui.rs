@@ -1,42 +1,79 @@
 use std::{rc::{Rc}, sync::{Arc, Mutex}};
 use slint::{ModelRc, SharedString, VecModel, Weak};
 
-use crate::minesweeper::{Minesweeper, Field, GameStatus};
+use crate::minesweeper::{Hint, Minesweeper, Field, GameStatus, Settings};
+use crate::replay::{MinesweeperReplay, MinesweeperSnapshot};
 
 slint::include_modules!();
 
 /// Runs a Minesweeper game with the corresponding ui.
 ///
 /// This function sets up the main window, initializes the Minesweeper game state, and registers
-/// callbacks for tile clicks, game restarts, and game ticks.
+/// callbacks for tile clicks, game restarts, game ticks, and save/load/replay.
 pub fn run(ms: Minesweeper) -> Result<(), slint::PlatformError> {
     let mw = MainWindow::new().unwrap();
     let msx: Arc<Mutex<Minesweeper>> = Arc::from(Mutex::from(ms));
-    
+    let replay: Arc<Mutex<Option<MinesweeperReplay>>> = Arc::from(Mutex::from(None));
+
     // callback: tile clicked
     mw.global::<TileLogic>().on_tile_clicked(tile_clicked_callback(
-        mw.as_weak(), 
-        msx.clone()
+        mw.as_weak(),
+        msx.clone(),
+        replay.clone()
     ));
 
     // callback: tile right clicked
     mw.global::<TileLogic>().on_tile_right_clicked(tile_right_clicked_callback(
-        mw.as_weak(), 
-        msx.clone()
+        mw.as_weak(),
+        msx.clone(),
+        replay.clone()
+    ));
+
+    // callback: tile chorded
+    mw.global::<TileLogic>().on_tile_chorded(tile_chorded_callback(
+        mw.as_weak(),
+        msx.clone(),
+        replay.clone()
     ));
 
     // callback: restart
     mw.global::<GameLogic>().on_restart(restart_callback(
-        mw.as_weak(), 
-        msx.clone()
+        mw.as_weak(),
+        msx.clone(),
+        replay.clone()
     ));
 
     // callback: tick
     mw.global::<GameLogic>().on_tick(tick_callback(
-        mw.as_weak(), 
+        mw.as_weak(),
+        msx.clone(),
+        replay.clone()
+    ));
+
+    // callback: save game
+    mw.global::<GameLogic>().on_save_game(save_game_callback(
         msx.clone()
     ));
-    
+
+    // callback: load game
+    mw.global::<GameLogic>().on_load_game(load_game_callback(
+        mw.as_weak(),
+        replay.clone()
+    ));
+
+    // callback: apply settings
+    mw.global::<GameLogic>().on_apply_settings(apply_settings_callback(
+        mw.as_weak(),
+        msx.clone(),
+        replay.clone()
+    ));
+
+    // callback: hint
+    mw.global::<GameLogic>().on_hint(hint_callback(
+        mw.as_weak(),
+        msx.clone()
+    ));
+
     // initial settings
     let ms = msx.lock().unwrap();
     mw.set_bombs_text(SharedString::from(format!("{:0>3}", ms.mine_count())));
@@ -48,10 +85,16 @@ pub fn run(ms: Minesweeper) -> Result<(), slint::PlatformError> {
 }
 
 fn board_as_model(board: Vec<Vec<Field>>) -> ModelRc<ModelRc<TileData>> {
+    return board_as_model_with_hint(board, None);
+}
+
+/// Builds the tile model for the given board, marking the tile at `hint` (if any) so the UI can
+/// render it distinctly.
+fn board_as_model_with_hint(board: Vec<Vec<Field>>, hint: Option<(usize, usize)>) -> ModelRc<ModelRc<TileData>> {
     let tiles: ModelRc<ModelRc<TileData>> = Rc::new(VecModel::from(
-        board.iter().map(|row| -> ModelRc<TileData> {
+        board.iter().enumerate().map(|(y, row)| -> ModelRc<TileData> {
             return Rc::new(VecModel::from(
-                row.iter().map(|col| -> TileData {
+                row.iter().enumerate().map(|(x, col)| -> TileData {
                     let mut td = TileData::default();
                     td.is_flagged = col.is_flagged();
                     td.revealed = col.is_revealed();
@@ -59,6 +102,7 @@ fn board_as_model(board: Vec<Vec<Field>>) -> ModelRc<ModelRc<TileData>> {
                         td.is_mine = col.is_mine();
                         td.adjacent_mines = col.adjacent_mines() as i32;
                     }
+                    td.hint = hint == Some((x, y));
                     return td;
                 }).collect::<Vec<TileData>>()
             )).clone().into();
@@ -70,18 +114,26 @@ fn board_as_model(board: Vec<Vec<Field>>) -> ModelRc<ModelRc<TileData>> {
 
 /// Creates a callback function to handle tile clicks.
 ///
+/// Does nothing while a replay is active, so the replay's board and the live game can't stomp
+/// on each other.
+///
 /// # Arguments
 ///
 /// * `weak_handle` - A weak reference to the main window handle.
 /// * `ms` - Rc reference to the Minesweeper game state.
+/// * `replay` - Rc reference to the currently active replay, if any.
 ///
 /// # Returns
 ///
 /// * A closure that handles the tile click logic, taking the x and y coordinates of the clicked tile as arguments.
-fn tile_clicked_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>) -> impl Fn(i32, i32) {
+fn tile_clicked_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>, replay: Arc<Mutex<Option<MinesweeperReplay>>>) -> impl Fn(i32, i32) {
     return move |x: i32, y: i32| {
+        if replay.lock().unwrap().is_some() {
+            return;
+        }
+
         let mut ms = ms.lock().unwrap();
-    
+
         ms.reveal(x as usize, y as usize);
         let ms_status = ms.status();
         let board = ms.board_clone();
@@ -100,18 +152,26 @@ fn tile_clicked_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweepe
 
 /// Creates a callback function to handle tile right clicks.
 ///
+/// Does nothing while a replay is active, so the replay's board and the live game can't stomp
+/// on each other.
+///
 /// # Arguments
 ///
 /// * `weak_handle` - A weak reference to the main window handle.
 /// * `ms` - Rc reference to the Minesweeper game state.
+/// * `replay` - Rc reference to the currently active replay, if any.
 ///
 /// # Returns
 ///
 /// * A closure that handles the tile right click logic, taking the x and y coordinates of the clicked tile as arguments.
-fn tile_right_clicked_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>) -> impl Fn(i32, i32) {
+fn tile_right_clicked_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>, replay: Arc<Mutex<Option<MinesweeperReplay>>>) -> impl Fn(i32, i32) {
     return move |x: i32, y: i32| {
+        if replay.lock().unwrap().is_some() {
+            return;
+        }
+
         let mut ms = ms.lock().unwrap();
-    
+
         ms.flag(x as usize, y as usize);
         let mines = ms.mine_count() - ms.flagged_count();
         let ms_status = ms.status();
@@ -131,18 +191,62 @@ fn tile_right_clicked_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Mine
     };
 }
 
+/// Creates a callback function to handle tile chords.
+///
+/// Does nothing while a replay is active, so the replay's board and the live game can't stomp
+/// on each other.
+///
+/// # Arguments
+///
+/// * `weak_handle` - A weak reference to the main window handle.
+/// * `ms` - Rc reference to the Minesweeper game state.
+/// * `replay` - Rc reference to the currently active replay, if any.
+///
+/// # Returns
+///
+/// * A closure that handles the tile chord logic, taking the x and y coordinates of the chorded tile as arguments.
+fn tile_chorded_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>, replay: Arc<Mutex<Option<MinesweeperReplay>>>) -> impl Fn(i32, i32) {
+    return move |x: i32, y: i32| {
+        if replay.lock().unwrap().is_some() {
+            return;
+        }
+
+        let mut ms = ms.lock().unwrap();
+
+        ms.chord(x as usize, y as usize);
+        let ms_status = ms.status();
+        let board = ms.board_clone();
+
+        weak_handle.upgrade_in_event_loop( move |handle| {
+            handle.set_status(match ms_status {
+                GameStatus::Running => UIGameStatus::Running,
+                GameStatus::Win => UIGameStatus::Win,
+                GameStatus::GameOver => UIGameStatus::GameOver,
+            });
+
+            handle.set_tiles(board_as_model(board));
+        }).unwrap();
+    };
+}
+
 /// Creates a callback function to handle game restarts.
 ///
+/// Clears any active replay before restarting, so the board re-renders from the live game
+/// instead of staying stuck on the replay's last frame.
+///
 /// # Arguments
 ///
 /// * `weak_handle` - A weak reference to the main window handle.
 /// * `ms` - Rc reference to the Minesweeper game state.
+/// * `replay` - Rc reference to the currently active replay, if any.
 ///
 /// # Returns
 ///
 /// * A closure that handles the game restart logic.
-fn restart_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>) -> impl Fn() {
+fn restart_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>, replay: Arc<Mutex<Option<MinesweeperReplay>>>) -> impl Fn() {
     return move || {
+        *replay.lock().unwrap() = None;
+
         let mut ms = ms.lock().unwrap();
 
         ms.restart();
@@ -165,18 +269,119 @@ fn restart_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>)
     }
 }
 
+/// Creates a callback function to handle applying new board settings from the settings panel.
+///
+/// Clears any active replay before reconfiguring, so the board re-renders from the live game
+/// instead of staying stuck on the replay's last frame.
+///
+/// # Arguments
+///
+/// * `weak_handle` - A weak reference to the main window handle.
+/// * `ms` - Rc reference to the Minesweeper game state.
+/// * `replay` - Rc reference to the currently active replay, if any.
+///
+/// # Returns
+///
+/// * A closure that reconfigures and restarts the game with the given width, height, and mine count.
+fn apply_settings_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>, replay: Arc<Mutex<Option<MinesweeperReplay>>>) -> impl Fn(i32, i32, i32) {
+    return move |width: i32, height: i32, mines: i32| {
+        *replay.lock().unwrap() = None;
+
+        let mut ms = ms.lock().unwrap();
+
+        ms.reconfigure(Settings { dx: width as usize, dy: height as usize, mine_count: mines as usize });
+        let mine_count = ms.mine_count();
+        let ms_status = ms.status();
+        let board = ms.board_clone();
+
+        weak_handle.upgrade_in_event_loop( move |handle| {
+            handle.set_timer_running(true);
+            handle.set_bombs_text(SharedString::from(format!("{:0>3}", mine_count)));
+            handle.set_time_text(SharedString::from("000"));
+            handle.set_status(match ms_status {
+                GameStatus::Running => UIGameStatus::Running,
+                GameStatus::Win => UIGameStatus::Win,
+                GameStatus::GameOver => UIGameStatus::GameOver,
+            });
+
+            handle.set_tiles(board_as_model(board));
+        }).unwrap();
+    }
+}
+
+/// Creates a callback function to handle hint requests.
+///
+/// Looks up the next logically guaranteed move and re-renders the board with that tile's `hint`
+/// flag set, so the UI can highlight it.
+///
+/// # Arguments
+///
+/// * `weak_handle` - A weak reference to the main window handle.
+/// * `ms` - Rc reference to the Minesweeper game state.
+///
+/// # Returns
+///
+/// * A closure that handles the hint logic.
+fn hint_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>) -> impl Fn() {
+    return move || {
+        let ms = ms.lock().unwrap();
+
+        let hint_pos = match ms.hint() {
+            Some(Hint::SafeReveal { x, y }) => Some((x, y)),
+            Some(Hint::Mine { x, y }) => Some((x, y)),
+            None => None,
+        };
+        let board = ms.board_clone();
+
+        weak_handle.upgrade_in_event_loop( move |handle| {
+            handle.set_tiles(board_as_model_with_hint(board, hint_pos));
+        }).unwrap();
+    }
+}
+
 /// Creates a callback function to handle game ticks.
 ///
+/// If a replay is active, each tick advances the replay by every recorded move that is due by
+/// the replay's elapsed time instead of advancing the timer, so `on_tick` doubles as the
+/// replay's playback clock.
+///
 /// # Arguments
 ///
 /// * `weak_handle` - A weak reference to the main window handle.
 /// * `ms` - Rc reference to the Minesweeper game state.
+/// * `replay` - Rc reference to the currently active replay, if any.
 ///
 /// # Returns
 ///
 /// * A closure that handles the game tick logic.
-fn tick_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>) -> impl Fn() {
+fn tick_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>, replay: Arc<Mutex<Option<MinesweeperReplay>>>) -> impl Fn() {
     return move || {
+        let mut replay_guard = replay.lock().unwrap();
+        if let Some(r) = replay_guard.as_mut() {
+            let board = r.advance();
+            let status = r.status();
+            let finished = r.is_finished();
+            if finished {
+                *replay_guard = None;
+            }
+            drop(replay_guard);
+
+            weak_handle.upgrade_in_event_loop(move |handle| {
+                handle.set_status(match status {
+                    GameStatus::Running => UIGameStatus::Running,
+                    GameStatus::Win => UIGameStatus::Win,
+                    GameStatus::GameOver => UIGameStatus::GameOver,
+                });
+
+                handle.set_tiles(board_as_model(board));
+                if finished {
+                    handle.set_timer_running(false);
+                }
+            }).unwrap();
+            return;
+        }
+        drop(replay_guard);
+
         let ms = ms.lock().unwrap();
         let secs = ms.seconds_running();
         let status = ms.status();
@@ -190,4 +395,58 @@ fn tick_callback(weak_handle: Weak<MainWindow>, ms: Arc<Mutex<Minesweeper>>) ->
             handle.set_time_text(SharedString::from(format!("{:0>3}", secs)));
         }).unwrap();
     }
+}
+
+/// Creates a callback function that serializes the current game to a JSON string for saving.
+///
+/// # Arguments
+///
+/// * `ms` - Rc reference to the Minesweeper game state.
+///
+/// # Returns
+///
+/// * A closure returning the serialized save game.
+fn save_game_callback(ms: Arc<Mutex<Minesweeper>>) -> impl Fn() -> SharedString {
+    return move || {
+        let ms = ms.lock().unwrap();
+        let snapshot = MinesweeperSnapshot::capture(&ms);
+
+        return SharedString::from(snapshot.save().unwrap_or_default());
+    };
+}
+
+/// Creates a callback function that loads a previously saved game and starts replaying it.
+///
+/// # Arguments
+///
+/// * `weak_handle` - A weak reference to the main window handle.
+/// * `replay` - Rc reference to the currently active replay, if any.
+///
+/// # Returns
+///
+/// * A closure that handles the load game logic, taking the saved game's JSON as an argument.
+fn load_game_callback(weak_handle: Weak<MainWindow>, replay: Arc<Mutex<Option<MinesweeperReplay>>>) -> impl Fn(SharedString) {
+    return move |json: SharedString| {
+        let snapshot = match MinesweeperSnapshot::load(json.as_str()) {
+            Ok(snapshot) => snapshot,
+            Err(_) => return, // malformed save file, ignore
+        };
+
+        let r = MinesweeperReplay::from_snapshot(&snapshot);
+        let board = r.board_clone();
+        let status = r.status();
+
+        *replay.lock().unwrap() = Some(r);
+
+        weak_handle.upgrade_in_event_loop(move |handle| {
+            handle.set_timer_running(true);
+            handle.set_status(match status {
+                GameStatus::Running => UIGameStatus::Running,
+                GameStatus::Win => UIGameStatus::Win,
+                GameStatus::GameOver => UIGameStatus::GameOver,
+            });
+
+            handle.set_tiles(board_as_model(board));
+        }).unwrap();
+    };
 }
\ No newline at end of file